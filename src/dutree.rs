@@ -0,0 +1,240 @@
+use crate::filter::Filter;
+use crate::size_filtered;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// A single node of a [`build_du_tree`] result: a path, its total size, and (unless collapsed by
+/// `max_depth` or `aggregate_threshold`) the same breakdown for its children.
+#[derive(Debug, Clone)]
+pub struct DuTree {
+    pub path: PathBuf,
+    pub size: u64,
+    pub children: Vec<DuTree>,
+}
+
+impl DuTree {
+    /// This node's size as a fraction of `parent_size`, for sizing a proportional usage bar.
+    pub fn fraction_of(&self, parent_size: u64) -> f64 {
+        if parent_size == 0 {
+            0.0
+        } else {
+            self.size as f64 / parent_size as f64
+        }
+    }
+}
+
+/// Builds a dutree-style usage tree for `path` in a single recursive scan.
+///
+/// Directories below `max_depth` are still sized (so totals stay accurate) but are not expanded
+/// into children, collapsing deep trees into a readable summary. Within a directory, any child
+/// whose size is less than `aggregate_threshold` of that directory's total is folded into a
+/// synthesized `<N others>` entry rather than cluttering the view with long tails of tiny entries.
+/// Pass `0.0` for `aggregate_threshold` to keep every child. `filter` is applied the same way
+/// [`crate::size_filtered`] applies it: an excluded entry is pruned from both the listing and the
+/// size total rather than merely hidden afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use durs::dutree::build_du_tree;
+/// use durs::filter::Filter;
+/// use std::path::Path;
+///
+/// let tree = build_du_tree(Path::new("/path/to/directory"), 2, 0.02, &Filter::new())?;
+/// println!("{}: {} bytes", tree.path.display(), tree.size);
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if `path` cannot be accessed or if there is an error reading
+/// the directory contents.
+pub fn build_du_tree<P: AsRef<Path>>(
+    path: P,
+    max_depth: usize,
+    aggregate_threshold: f64,
+    filter: &Filter,
+) -> anyhow::Result<DuTree> {
+    build(path.as_ref(), 0, max_depth, aggregate_threshold, filter)
+}
+
+fn build(
+    path: &Path,
+    depth: usize,
+    max_depth: usize,
+    threshold: f64,
+    filter: &Filter,
+) -> anyhow::Result<DuTree> {
+    let meta = path.symlink_metadata()?;
+    if !meta.is_dir() {
+        return Ok(DuTree {
+            path: path.to_path_buf(),
+            size: meta.len(),
+            children: Vec::new(),
+        });
+    }
+
+    if depth >= max_depth {
+        return Ok(DuTree {
+            path: path.to_path_buf(),
+            size: size_filtered(path, filter),
+            children: Vec::new(),
+        });
+    }
+
+    // A directory that can't be read, or a child that can't be sized (removed mid-scan, a broken
+    // symlink, ...), is skipped rather than aborting the whole tree, mirroring the walker's
+    // per-entry error handling instead of propagating the first error with `?`.
+    let mut children = Vec::new();
+    if let Ok(read_dir) = path.read_dir() {
+        for entry in read_dir.flatten() {
+            let entry_path = entry.path();
+            if !filter.matches(&entry_path) {
+                continue;
+            }
+            if let Ok(child) = build(&entry_path, depth + 1, max_depth, threshold, filter) {
+                children.push(child);
+            }
+        }
+    }
+    let total: u64 = children.iter().map(|child| child.size).sum();
+    children = fold_below_threshold(path, children, total, threshold);
+    children.sort_by_key(|child| Reverse(child.size));
+
+    Ok(DuTree {
+        path: path.to_path_buf(),
+        size: total,
+        children,
+    })
+}
+
+fn fold_below_threshold(
+    parent: &Path,
+    children: Vec<DuTree>,
+    total: u64,
+    threshold: f64,
+) -> Vec<DuTree> {
+    if threshold <= 0.0 || total == 0 {
+        return children;
+    }
+
+    let mut kept = Vec::new();
+    let mut folded_size = 0u64;
+    let mut folded_count = 0usize;
+    for child in children {
+        if child.fraction_of(total) < threshold {
+            folded_size += child.size;
+            folded_count += 1;
+        } else {
+            kept.push(child);
+        }
+    }
+
+    if folded_count > 0 {
+        kept.push(DuTree {
+            path: parent.join(format!("<{folded_count} others>")),
+            size: folded_size,
+            children: Vec::new(),
+        });
+    }
+    kept
+}
+
+/// Formats `bytes` as a human-readable size using binary (KiB/MiB/GiB) units.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Renders a proportional horizontal bar of `width` characters, `fraction` of it filled.
+pub fn bar(fraction: f64, width: usize) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * width as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), " ".repeat(width - filled))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+    use std::io::Write;
+
+    #[test]
+    fn test_build_du_tree() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_build_du_tree");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let mut big = File::create(temp_dir.join("big"))?;
+        write!(big, "{}", "x".repeat(100))?;
+
+        let mut small = File::create(temp_dir.join("small"))?;
+        write!(small, "x")?;
+
+        let tree = build_du_tree(&temp_dir, 1, 0.0, &Filter::new())?;
+        assert_eq!(tree.size, 101);
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].size, 100);
+        assert_eq!(tree.children[1].size, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_du_tree_folds_below_threshold() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_build_du_tree_threshold");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let mut big = File::create(temp_dir.join("big"))?;
+        write!(big, "{}", "x".repeat(1000))?;
+
+        let mut tiny = File::create(temp_dir.join("tiny"))?;
+        write!(tiny, "x")?;
+
+        let tree = build_du_tree(&temp_dir, 1, 0.5, &Filter::new())?;
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].path, temp_dir.join("big"));
+        assert_eq!(tree.children[1].path, temp_dir.join("<1 others>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_du_tree_respects_max_depth() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_build_du_tree_max_depth");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let nested = temp_dir.join("nested");
+        create_dir_all(&nested)?;
+        let mut file = File::create(nested.join("file"))?;
+        write!(file, "test")?;
+
+        let tree = build_du_tree(&temp_dir, 0, 0.0, &Filter::new())?;
+        assert_eq!(tree.size, 4);
+        assert!(tree.children.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(2048), "2.0 KiB");
+    }
+}
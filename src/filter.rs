@@ -0,0 +1,72 @@
+use regex::Regex;
+use std::path::Path;
+
+/// A compiled set of `--exclude`/`--include` patterns, as dutree compiles its `--aggr` patterns.
+///
+/// A path is kept if it matches no exclude pattern, and either no include patterns were given or
+/// it matches at least one of them. Exclude always wins over include.
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    excludes: Vec<Regex>,
+    includes: Vec<Regex>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compiles `excludes` and `includes` into a `Filter`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any pattern is not a valid regex.
+    pub fn compile(excludes: &[String], includes: &[String]) -> anyhow::Result<Self> {
+        let excludes = excludes.iter().map(|p| Regex::new(p)).collect::<Result<_, _>>()?;
+        let includes = includes.iter().map(|p| Regex::new(p)).collect::<Result<_, _>>()?;
+        Ok(Self { excludes, includes })
+    }
+
+    /// Returns whether no `--exclude`/`--include` patterns were given, i.e. every path is kept.
+    pub fn is_empty(&self) -> bool {
+        self.excludes.is_empty() && self.includes.is_empty()
+    }
+
+    /// Returns whether `path` should be kept: not matched by any exclude pattern, and matched by
+    /// an include pattern whenever at least one was given.
+    pub fn matches(&self, path: &Path) -> bool {
+        let text = path.to_string_lossy();
+        if self.excludes.iter().any(|re| re.is_match(&text)) {
+            return false;
+        }
+        self.includes.is_empty() || self.includes.iter().any(|re| re.is_match(&text))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter_excludes() -> anyhow::Result<()> {
+        let filter = Filter::compile(&[r"\.log$".to_string()], &[])?;
+        assert!(!filter.matches(Path::new("/tmp/app.log")));
+        assert!(filter.matches(Path::new("/tmp/app.rs")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_includes() -> anyhow::Result<()> {
+        let filter = Filter::compile(&[], &[r"\.rs$".to_string()])?;
+        assert!(filter.matches(Path::new("/tmp/app.rs")));
+        assert!(!filter.matches(Path::new("/tmp/app.log")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_exclude_wins_over_include() -> anyhow::Result<()> {
+        let filter = Filter::compile(&["target".to_string()], &[r"\.rs$".to_string()])?;
+        assert!(!filter.matches(Path::new("/tmp/target/app.rs")));
+        Ok(())
+    }
+}
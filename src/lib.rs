@@ -1,6 +1,17 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::{fs::DirEntry, path::PathBuf};
 
+pub mod dutree;
+pub mod filter;
+pub mod walk;
+
+use filter::Filter;
+
 /// Lists the contents of the specified path.
 ///
 /// If the path is a directory, this function returns a vector of `PathBuf` objects representing
@@ -38,8 +49,9 @@ pub fn ls<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<PathBuf>> {
 
 /// Recursively lists the contents of the specified path and all its subdirectories.
 ///
-/// This function returns a vector of `PathBuf` objects representing all the files and directories
-/// within the specified path and its subdirectories.
+/// Built on [`walk::walk`] with default options, so symlinks are not followed (a symlink loop
+/// cannot make it loop forever) and a single unreadable subdirectory is skipped rather than
+/// aborting the whole listing. The walker's per-entry errors are not returned.
 ///
 /// # Examples
 ///
@@ -47,32 +59,36 @@ pub fn ls<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<PathBuf>> {
 /// use durs::ls_rec;
 /// use std::path::Path;
 ///
-/// let files = ls_rec(Path::new("/path/to/directory"))?;
+/// let files = ls_rec(Path::new("/path/to/directory"));
 /// for file in files {
 ///     println!("{}", file.display());
 /// }
 /// ```
+pub fn ls_rec<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+    walk::walk(path, &walk::WalkOptions::new()).entries
+}
+
+/// Like [`ls_rec`], but skips any entry excluded by `filter`; an excluded directory is pruned
+/// rather than merely omitted from the results, so it is never descended into.
 ///
-/// # Errors
+/// Built on top of [`walk::walk_filtered`], so a single unreadable subdirectory is skipped rather
+/// than aborting the whole scan. The walker's per-entry errors are not returned.
 ///
-/// This function will return an error if the path cannot be accessed or if there is an error
-/// reading the directory contents.
-pub fn ls_rec<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<PathBuf>> {
-    let meta = path.as_ref().symlink_metadata()?;
-    let mut entries = Vec::new();
-    if meta.is_dir() {
-        for entry in path.as_ref().read_dir()? {
-            let entry = entry?;
-            let entry_meta = entry.metadata()?;
-            entries.push(entry.path());
-            if entry_meta.is_dir() {
-                entries.append(&mut ls_rec(entry.path())?);
-            }
-        }
-    } else {
-        entries.push(path.as_ref().to_path_buf());
-    }
-    Ok(entries)
+/// # Examples
+///
+/// ```
+/// use durs::filter::Filter;
+/// use durs::ls_rec_filtered;
+/// use std::path::Path;
+///
+/// let filter = Filter::compile(&[r"\.log$".to_string()], &[])?;
+/// let files = ls_rec_filtered(Path::new("/path/to/directory"), &filter);
+/// for file in files {
+///     println!("{}", file.display());
+/// }
+/// ```
+pub fn ls_rec_filtered<P: AsRef<Path>>(path: P, filter: &Filter) -> Vec<PathBuf> {
+    walk::walk_filtered(path, &walk::WalkOptions::new(), |p| filter.matches(p)).entries
 }
 
 /// Calculates the total size of the specified path and its contents.
@@ -114,6 +130,206 @@ pub fn size<P: AsRef<Path>>(path: P) -> anyhow::Result<u64> {
     Ok(bytes)
 }
 
+/// Like [`size`], but skips any entry excluded by `filter`; an excluded directory is pruned from
+/// the total rather than having its size computed and discarded.
+///
+/// Built on [`walk::walk_filtered`], so — unlike [`size`] — a single unreadable subdirectory
+/// anywhere under `path` is skipped rather than aborting the whole calculation.
+///
+/// # Examples
+///
+/// ```
+/// use durs::filter::Filter;
+/// use durs::size_filtered;
+/// use std::path::Path;
+///
+/// let filter = Filter::compile(&["target".to_string()], &[])?;
+/// let total_size = size_filtered(Path::new("/path/to/directory"), &filter);
+/// println!("Total size: {} bytes", total_size);
+/// ```
+pub fn size_filtered<P: AsRef<Path>>(path: P, filter: &Filter) -> u64 {
+    let result = walk::walk_filtered(path, &walk::WalkOptions::new(), |p| filter.matches(p));
+    result
+        .entries
+        .iter()
+        .filter_map(|entry| entry.symlink_metadata().ok())
+        .filter(|meta| !meta.is_dir())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Calculates the total size of `path` using a work-stealing pool of threads, for scanning large
+/// trees without blocking the caller for the whole traversal.
+///
+/// Each worker pops a pending directory from a shared queue, reads it, adds its files' sizes to
+/// `progress`, and pushes any subdirectories back onto the queue for any worker to pick up.
+/// `progress` is updated incrementally as entries are visited, so a caller such as the TUI event
+/// loop can poll it between `terminal.draw` calls to show a live running total. Setting `cancel`
+/// makes every worker stop picking up new directories and return promptly, leaving `progress` at
+/// whatever partial total had accumulated so far.
+///
+/// # Examples
+///
+/// ```
+/// use durs::size_parallel;
+/// use std::path::Path;
+/// use std::sync::atomic::{AtomicBool, AtomicU64};
+/// use std::sync::Arc;
+///
+/// let progress = Arc::new(AtomicU64::new(0));
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let total = size_parallel(Path::new("/path/to/directory"), progress, cancel)?;
+/// println!("Total size: {} bytes", total);
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if `path` itself cannot be accessed. Errors encountered
+/// while reading subdirectories are skipped so one unreadable entry doesn't abort the whole scan.
+pub fn size_parallel<P: AsRef<Path>>(
+    path: P,
+    progress: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+) -> anyhow::Result<u64> {
+    let meta = path.as_ref().symlink_metadata()?;
+    if !meta.is_dir() {
+        progress.fetch_add(meta.len(), Ordering::Relaxed);
+        return Ok(progress.load(Ordering::Relaxed));
+    }
+
+    let queue = Mutex::new(VecDeque::from([path.as_ref().to_path_buf()]));
+    let pending = AtomicUsize::new(1);
+    let num_workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+
+    std::thread::scope(|scope| {
+        for _ in 0..num_workers {
+            scope.spawn(|| loop {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                let dir = queue.lock().unwrap().pop_front();
+                let dir = match dir {
+                    Some(dir) => dir,
+                    None => {
+                        if pending.load(Ordering::Acquire) == 0 {
+                            return;
+                        }
+                        std::thread::yield_now();
+                        continue;
+                    }
+                };
+
+                if let Ok(read_dir) = std::fs::read_dir(&dir) {
+                    for entry in read_dir.flatten() {
+                        let Ok(entry_meta) = entry.metadata() else {
+                            continue;
+                        };
+                        if entry_meta.is_dir() {
+                            pending.fetch_add(1, Ordering::AcqRel);
+                            queue.lock().unwrap().push_back(entry.path());
+                        } else {
+                            progress.fetch_add(entry_meta.len(), Ordering::Relaxed);
+                        }
+                    }
+                }
+                pending.fetch_sub(1, Ordering::AcqRel);
+            });
+        }
+    });
+
+    Ok(progress.load(Ordering::Relaxed))
+}
+
+/// Which notion of a file's size to count, mirroring `du`'s `--apparent-size` switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeMode {
+    /// The logical file size (`metadata.len()`), as `size` reports. Sparse files are counted at
+    /// face value, which can overstate actual disk usage; hard links are counted once per link
+    /// unless `dedup_hardlinks` is set.
+    Apparent,
+    /// The space actually allocated on disk (`blocks() * 512`), correctly accounting for sparse
+    /// files.
+    Allocated,
+}
+
+/// Calculates the total size of `path` the way `du` does: using `mode` to choose between apparent
+/// and allocated size, and optionally deduplicating hard-linked files so each inode is only
+/// counted once.
+///
+/// When `dedup_hardlinks` is set, every file with a link count greater than one has its
+/// `(st_dev, st_ino)` recorded the first time it is encountered; subsequent encounters of the same
+/// inode elsewhere in the tree contribute zero to the total, avoiding the double-counting that
+/// [`size`] is prone to.
+///
+/// A subdirectory that can't be read, or an entry that can't be sized, is skipped rather than
+/// aborting the whole calculation — unlike [`size`], a single unreadable entry anywhere under
+/// `path` doesn't turn the whole call into an `Err`.
+///
+/// # Examples
+///
+/// ```
+/// use durs::{size_du, SizeMode};
+/// use std::path::Path;
+///
+/// let total = size_du(Path::new("/path/to/directory"), SizeMode::Allocated, true)?;
+/// println!("Disk usage: {} bytes", total);
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if `path` itself cannot be accessed.
+pub fn size_du<P: AsRef<Path>>(path: P, mode: SizeMode, dedup_hardlinks: bool) -> anyhow::Result<u64> {
+    let mut seen = HashSet::new();
+    size_du_inner(path.as_ref(), mode, dedup_hardlinks, &mut seen)
+}
+
+fn size_du_inner(
+    path: &Path,
+    mode: SizeMode,
+    dedup_hardlinks: bool,
+    seen: &mut HashSet<(u64, u64)>,
+) -> anyhow::Result<u64> {
+    let meta = path.symlink_metadata()?;
+    if !meta.is_dir() {
+        return Ok(entry_size(&meta, mode, dedup_hardlinks, seen));
+    }
+
+    // A directory that can't be read, or a child that can't be sized (permission denied, removed
+    // mid-scan, ...), is skipped rather than aborting the whole total, mirroring the walker's
+    // per-entry error handling instead of propagating the first error with `?`.
+    let mut bytes = 0;
+    if let Ok(read_dir) = path.read_dir() {
+        for entry in read_dir.flatten() {
+            let Ok(entry_meta) = entry.metadata() else {
+                continue;
+            };
+            if entry_meta.is_dir() {
+                if let Ok(child) = size_du_inner(&entry.path(), mode, dedup_hardlinks, seen) {
+                    bytes += child;
+                }
+            } else {
+                bytes += entry_size(&entry_meta, mode, dedup_hardlinks, seen);
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+fn entry_size(meta: &Metadata, mode: SizeMode, dedup_hardlinks: bool, seen: &mut HashSet<(u64, u64)>) -> u64 {
+    if dedup_hardlinks && meta.nlink() > 1 {
+        let id = (meta.dev(), meta.ino());
+        if !seen.insert(id) {
+            return 0;
+        }
+    }
+    match mode {
+        SizeMode::Apparent => meta.len(),
+        SizeMode::Allocated => meta.blocks() * 512,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -146,6 +362,121 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_size_parallel() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_size_parallel");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let mut file = File::create(temp_dir.join("file"))?;
+        write!(file, "test"); // 4 bytes
+
+        let dir_path = temp_dir.join("dir");
+        create_dir_all(&dir_path)?;
+
+        let mut file = File::create(dir_path.join("other_file"))?;
+        write!(file, "testing test"); // 12 bytes
+
+        let progress = Arc::new(AtomicU64::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let total = size_parallel(&temp_dir, progress.clone(), cancel)?;
+
+        assert_eq!(total, 4 + 12);
+        assert_eq!(progress.load(Ordering::Relaxed), total);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_du_apparent_matches_size() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_size_du_apparent");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let mut file = File::create(temp_dir.join("file"))?;
+        write!(file, "test"); // 4 bytes
+
+        assert_eq!(
+            size_du(&temp_dir, SizeMode::Apparent, false)?,
+            size(&temp_dir)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_du_dedups_hardlinks() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_size_du_hardlinks");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let file_path = temp_dir.join("file");
+        let mut file = File::create(&file_path)?;
+        write!(file, "test")?; // 4 bytes
+        drop(file);
+
+        let link_path = temp_dir.join("hardlink");
+        std::fs::hard_link(&file_path, &link_path)?;
+
+        assert_eq!(size_du(&temp_dir, SizeMode::Apparent, false)?, 8);
+        assert_eq!(size_du(&temp_dir, SizeMode::Apparent, true)?, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_filtered_excludes_matching_entries() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_size_filtered");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let mut file = File::create(temp_dir.join("file.rs"))?;
+        write!(file, "test")?; // 4 bytes
+
+        let mut file = File::create(temp_dir.join("file.log"))?;
+        write!(file, "testing test")?; // 12 bytes
+
+        let filter = Filter::compile(&[r"\.log$".to_string()], &[])?;
+        assert_eq!(size_filtered(&temp_dir, &filter), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ls_rec_filtered_prunes_excluded_dirs() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_ls_rec_filtered");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let kept_dir = temp_dir.join("src");
+        create_dir_all(&kept_dir)?;
+        let kept_file = kept_dir.join("main.rs");
+        File::create(&kept_file)?;
+
+        let excluded_dir = temp_dir.join("target");
+        create_dir_all(&excluded_dir)?;
+        File::create(excluded_dir.join("artifact"))?;
+
+        let filter = Filter::compile(&["target".to_string()], &[])?;
+        let mut entries = ls_rec_filtered(&temp_dir, &filter);
+        entries.sort();
+        let mut expected = vec![kept_dir, kept_file];
+        expected.sort();
+        assert_eq!(entries, expected);
+
+        Ok(())
+    }
+
     #[test]
     fn test_ls_dir() -> anyhow::Result<()> {
         let temp_dir = std::env::temp_dir().join("durs_test_ls_dir");
@@ -205,7 +536,7 @@ mod test {
         let file_path_from_dir = dir_path.join("file");
         let _ = File::create(&file_path_from_dir)?;
 
-        let mut actual = ls_rec(&temp_dir)?;
+        let mut actual = ls_rec(&temp_dir);
         actual.sort();
         let mut expected = vec![file_path, dir_path, file_path_from_dir];
         expected.sort();
@@ -225,8 +556,8 @@ mod test {
         let file_path = temp_dir.join("file");
         let _ = File::create(&file_path)?;
 
-        let mut actual = ls_rec(&file_path)?;
-        let mut expected = vec![file_path];
+        let actual = ls_rec(&file_path);
+        let expected = vec![file_path];
         assert_eq!(actual, expected);
 
         Ok(())
@@ -1,9 +1,29 @@
 use clap::Parser;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
-use ratatui::{Frame, text::Text};
+use durs::dutree::{bar, build_du_tree, human_size, DuTree};
+use durs::filter::Filter;
+use durs::{ls, size_du, size_filtered, size_parallel, SizeMode};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+/// How many levels of the disk-usage tree view are expanded before being folded into a summary.
+const DUTREE_MAX_DEPTH: usize = 2;
+/// Children smaller than this fraction of their parent are folded into a `<N others>` entry.
+const DUTREE_AGGREGATE_THRESHOLD: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Browser,
+    DuTree,
+}
+
 #[derive(Parser, Debug)]
 struct Args {
     /// Path to directory
@@ -12,31 +32,216 @@ struct Args {
         value_hint = clap::ValueHint::DirPath
     )]
     path: PathBuf,
+
+    /// Exclude paths matching this regex (repeatable); excluded directories are pruned, not
+    /// descended into
+    #[arg(long = "exclude")]
+    exclude: Vec<String>,
+
+    /// Only include paths matching this regex (repeatable); has no effect unless given
+    #[arg(long = "include")]
+    include: Vec<String>,
+
+    /// Report allocated disk space (as `du` does) instead of each file's apparent logical size in
+    /// the directory browser; the disk-usage tree view still shows apparent sizes. With this flag,
+    /// --exclude/--include filter the listed entries but not the paths nested below them
+    #[arg(long = "disk-usage")]
+    disk_usage: bool,
+
+    /// Count each hard-linked file only once; has no effect unless --disk-usage is also given
+    #[arg(long = "dedup-hardlinks")]
+    dedup_hardlinks: bool,
 }
 
-struct App {
+struct Entry {
     path: PathBuf,
+    size: u64,
+}
+
+/// A size scan running on a background thread, so the browser stays responsive while a large
+/// directory is being sized. `progress` is polled between `terminal.draw` calls to show a live
+/// running total; setting `cancel` makes the thread stop picking up new entries and wind down.
+struct Scan {
+    progress: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<Vec<Entry>>,
+}
+
+impl Scan {
+    fn spawn(dir: PathBuf, filter: Filter, disk_usage: bool, dedup_hardlinks: bool) -> Self {
+        let progress = Arc::new(AtomicU64::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress_for_thread = Arc::clone(&progress);
+        let cancel_for_thread = Arc::clone(&cancel);
+
+        let handle = thread::spawn(move || {
+            let mut entries: Vec<Entry> = ls(&dir)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|path| filter.matches(path))
+                .take_while(|_| !cancel_for_thread.load(Ordering::Relaxed))
+                .map(|path| {
+                    // size_du can't prune nested excludes/includes either, but --disk-usage is an
+                    // explicit, narrower opt-in than the default path, so it takes priority over
+                    // the filter-aware size_filtered when both are in play. Otherwise, size_parallel
+                    // only supports the common case of no filter; a filter active without
+                    // --disk-usage falls back to the (single-threaded but still off the UI thread)
+                    // filter-aware size_filtered.
+                    let size = if disk_usage {
+                        size_du(&path, SizeMode::Allocated, dedup_hardlinks).unwrap_or(0)
+                    } else if filter.is_empty() {
+                        size_parallel(&path, Arc::new(AtomicU64::new(0)), Arc::clone(&cancel_for_thread))
+                            .unwrap_or(0)
+                    } else {
+                        size_filtered(&path, &filter)
+                    };
+                    progress_for_thread.fetch_add(size, Ordering::Relaxed);
+                    Entry { path, size }
+                })
+                .collect();
+            entries.sort_by(|a, b| b.size.cmp(&a.size));
+            entries
+        });
+
+        Self {
+            progress,
+            cancel,
+            handle,
+        }
+    }
+
+    fn cancel(self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        // Deliberately don't join: the thread notices `cancel` on its next iteration and winds
+        // down on its own, but the UI must not block waiting for that to happen.
+    }
+}
+
+struct App {
+    current_dir: PathBuf,
+    entries: Vec<Entry>,
+    selected: usize,
+    history: Vec<(PathBuf, Vec<Entry>, usize)>,
     running: bool,
+    view: View,
+    dutree: Option<DuTree>,
+    filter: Filter,
+    disk_usage: bool,
+    dedup_hardlinks: bool,
+    scan: Option<Scan>,
 }
 
 impl App {
-    fn new(path: PathBuf) -> Self {
-        Self {
-            path,
+    fn new(path: PathBuf, filter: Filter, disk_usage: bool, dedup_hardlinks: bool) -> Self {
+        let mut app = Self {
+            current_dir: path,
+            entries: Vec::new(),
+            selected: 0,
+            history: Vec::new(),
             running: true,
+            view: View::Browser,
+            dutree: None,
+            filter,
+            disk_usage,
+            dedup_hardlinks,
+            scan: None,
+        };
+        app.load_entries();
+        app
+    }
+
+    fn load_entries(&mut self) {
+        self.cancel_scan();
+        self.entries = Vec::new();
+        self.selected = 0;
+        self.scan = Some(Scan::spawn(
+            self.current_dir.clone(),
+            self.filter.clone(),
+            self.disk_usage,
+            self.dedup_hardlinks,
+        ));
+    }
+
+    /// Picks up the background scan's result once it has finished; call once per event-loop tick.
+    fn poll_scan(&mut self) {
+        let finished = self.scan.as_ref().is_some_and(|scan| scan.handle.is_finished());
+        if finished {
+            if let Some(scan) = self.scan.take() {
+                if let Ok(entries) = scan.handle.join() {
+                    self.entries = entries;
+                }
+            }
+        }
+    }
+
+    fn cancel_scan(&mut self) {
+        if let Some(scan) = self.scan.take() {
+            scan.cancel();
         }
     }
 
     fn on_key(&mut self, key: char) {
         match key {
-            'q' | 'Q' => self.running = false,
+            'q' | 'Q' => {
+                self.cancel_scan();
+                self.running = false;
+            }
+            't' | 'T' => self.toggle_view(),
             _ => {}
         }
     }
-    fn on_down(&self) {}
-    fn on_left(&self) {}
-    fn on_right(&self) {}
-    fn on_up(&self) {}
+
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Browser => {
+                self.dutree = build_du_tree(
+                    &self.current_dir,
+                    DUTREE_MAX_DEPTH,
+                    DUTREE_AGGREGATE_THRESHOLD,
+                    &self.filter,
+                )
+                .ok();
+                View::DuTree
+            }
+            View::DuTree => View::Browser,
+        };
+    }
+
+    fn on_down(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1).min(self.entries.len() - 1);
+        }
+    }
+
+    fn on_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    fn on_right(&mut self) {
+        let Some(entry) = self.entries.get(self.selected) else {
+            return;
+        };
+        if !entry.path.is_dir() {
+            return;
+        }
+        let next_dir = entry.path.clone();
+        self.history.push((
+            std::mem::replace(&mut self.current_dir, next_dir),
+            std::mem::take(&mut self.entries),
+            self.selected,
+        ));
+        self.load_entries();
+    }
+
+    fn on_left(&mut self) {
+        if let Some((dir, entries, selected)) = self.history.pop() {
+            self.cancel_scan();
+            self.current_dir = dir;
+            self.entries = entries;
+            self.selected = selected;
+        }
+    }
+
     fn shuld_close(&self) -> bool {
         !self.running
     }
@@ -44,19 +249,26 @@ impl App {
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    let mut app = App::new(args.path);
+    let filter = Filter::compile(&args.exclude, &args.include)?;
+    let mut app = App::new(args.path, filter, args.disk_usage, args.dedup_hardlinks);
     let mut terminal = ratatui::init();
     while !app.shuld_close() {
-        terminal.draw(draw)?;
-        if event::poll(Duration::from_secs(2))? {
+        app.poll_scan();
+        terminal.draw(|frame| draw(&app, frame))?;
+        let poll_timeout = if app.scan.is_some() {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(2)
+        };
+        if event::poll(poll_timeout)? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     match key.code {
-                        KeyCode::Char(c) => app.on_key(c),
                         KeyCode::Down | KeyCode::Char('j') => app.on_down(),
                         KeyCode::Left | KeyCode::Char('h') => app.on_left(),
                         KeyCode::Right | KeyCode::Char('l') => app.on_right(),
                         KeyCode::Up | KeyCode::Char('k') => app.on_up(),
+                        KeyCode::Char(c) => app.on_key(c),
                         _ => {}
                     }
                 }
@@ -67,7 +279,86 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn draw(frame: &mut Frame) {
-    let text = Text::raw("Hello World!");
-    frame.render_widget(text, frame.area());
+fn draw(app: &App, frame: &mut Frame) {
+    match app.view {
+        View::Browser => draw_browser(app, frame),
+        View::DuTree => draw_dutree(app, frame),
+    }
+}
+
+fn draw_browser(app: &App, frame: &mut Frame) {
+    let items: Vec<ListItem> = app
+        .entries
+        .iter()
+        .map(|entry| {
+            let name = entry
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| entry.path.display().to_string());
+            ListItem::new(format!("{:>12} {}", entry.size, name))
+        })
+        .collect();
+
+    let title = match &app.scan {
+        Some(scan) => format!(
+            "{} (scanning... {} so far)",
+            app.current_dir.display(),
+            human_size(scan.progress.load(Ordering::Relaxed))
+        ),
+        None => app.current_dir.display().to_string(),
+    };
+
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.selected));
+
+    frame.render_stateful_widget(list, frame.area(), &mut state);
+}
+
+fn draw_dutree(app: &App, frame: &mut Frame) {
+    let Some(tree) = &app.dutree else {
+        frame.render_widget(
+            Paragraph::new("no scan available").block(Block::bordered().title("disk usage")),
+            frame.area(),
+        );
+        return;
+    };
+
+    let area = frame.area();
+    let bar_width = (area.width as usize).saturating_sub(40).max(10);
+    let max_sibling_size = tree.children.iter().map(|child| child.size).max().unwrap_or(0);
+    let lines: Vec<Line> = tree
+        .children
+        .iter()
+        .map(|child| {
+            let percent = child.fraction_of(tree.size);
+            let bar_fraction = child.fraction_of(max_sibling_size);
+            let name = child
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| child.path.display().to_string());
+            Line::from(format!(
+                "{:>9} {:>6.1}% {} {}",
+                human_size(child.size),
+                percent * 100.0,
+                bar(bar_fraction, bar_width),
+                name
+            ))
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::bordered().title(format!(
+            "{} ({})",
+            tree.path.display(),
+            human_size(tree.size)
+        )),
+    );
+    frame.render_widget(paragraph, area);
 }
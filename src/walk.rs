@@ -0,0 +1,281 @@
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// Options controlling how [`walk`] traverses a directory tree.
+///
+/// # Examples
+///
+/// ```
+/// use durs::walk::WalkOptions;
+///
+/// let opts = WalkOptions::new().max_depth(Some(2)).follow_links(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WalkOptions {
+    pub max_depth: Option<usize>,
+    pub min_depth: usize,
+    pub follow_links: bool,
+}
+
+impl WalkOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    pub fn follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+}
+
+/// An error encountered while visiting a single entry during a [`walk`].
+///
+/// Unlike an error in [`size`](crate::size), a `WalkError` does not abort the rest of the traversal; it is
+/// collected into [`WalkResult::errors`] so the caller can decide how to report it.
+#[derive(Debug)]
+pub struct WalkError {
+    pub path: PathBuf,
+    pub error: anyhow::Error,
+}
+
+/// The outcome of a [`walk`]: every path visited, plus any per-entry errors.
+#[derive(Debug, Default)]
+pub struct WalkResult {
+    pub entries: Vec<PathBuf>,
+    pub errors: Vec<WalkError>,
+}
+
+fn dir_id(meta: &Metadata) -> (u64, u64) {
+    (meta.dev(), meta.ino())
+}
+
+/// Iteratively walks `path` and its subdirectories, modeled on walkdir.
+///
+/// Traversal uses an explicit stack rather than recursion, so it cannot blow the stack on deep
+/// trees. Each visited directory's `(dev, ino)` is recorded before it is descended into; if
+/// `follow_links` is set and a symlink leads back to an already-visited directory, the cycle is
+/// reported as a "loop detected" [`WalkError`] instead of being followed. A directory that cannot
+/// be read (permission denied, removed mid-walk, ...) contributes a [`WalkError`] for that entry
+/// and the walk continues with its siblings.
+///
+/// # Examples
+///
+/// ```
+/// use durs::walk::{walk, WalkOptions};
+/// use std::path::Path;
+///
+/// let result = walk(Path::new("/path/to/directory"), &WalkOptions::new());
+/// for path in &result.entries {
+///     println!("{}", path.display());
+/// }
+/// for err in &result.errors {
+///     eprintln!("{}: {}", err.path.display(), err.error);
+/// }
+/// ```
+pub fn walk<P: AsRef<Path>>(path: P, opts: &WalkOptions) -> WalkResult {
+    walk_inner(path.as_ref(), opts, &|_: &Path| true)
+}
+
+/// Like [`walk`], but skips any entry for which `filter` returns `false`. An excluded directory is
+/// pruned rather than merely omitted from the results: it is never descended into, so filtering is
+/// efficient even when it rules out the bulk of a large tree.
+///
+/// # Examples
+///
+/// ```
+/// use durs::walk::{walk_filtered, WalkOptions};
+/// use std::path::Path;
+///
+/// let result = walk_filtered(Path::new("/path/to/directory"), &WalkOptions::new(), |path| {
+///     path.extension().is_none_or(|ext| ext != "log")
+/// });
+/// ```
+pub fn walk_filtered<P: AsRef<Path>>(
+    path: P,
+    opts: &WalkOptions,
+    filter: impl Fn(&Path) -> bool,
+) -> WalkResult {
+    walk_inner(path.as_ref(), opts, &filter)
+}
+
+fn walk_inner(path: &Path, opts: &WalkOptions, filter: &dyn Fn(&Path) -> bool) -> WalkResult {
+    let mut result = WalkResult::default();
+    let root = path.to_path_buf();
+
+    let root_meta = match root.symlink_metadata() {
+        Ok(meta) => meta,
+        Err(e) => {
+            result.errors.push(WalkError {
+                path: root,
+                error: e.into(),
+            });
+            return result;
+        }
+    };
+
+    if !root_meta.is_dir() {
+        if opts.min_depth == 0 {
+            result.entries.push(root);
+        }
+        return result;
+    }
+
+    let mut visited = HashSet::new();
+    visited.insert(dir_id(&root_meta));
+
+    let mut stack = vec![(root, 0usize)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                result.errors.push(WalkError {
+                    path: dir,
+                    error: e.into(),
+                });
+                continue;
+            }
+        };
+
+        for entry in read_dir {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    result.errors.push(WalkError {
+                        path: dir.clone(),
+                        error: e.into(),
+                    });
+                    continue;
+                }
+            };
+            let entry_path = entry.path();
+            let child_depth = depth + 1;
+
+            if !filter(&entry_path) {
+                continue;
+            }
+
+            let meta = if opts.follow_links {
+                fs::metadata(&entry_path)
+            } else {
+                entry.metadata()
+            };
+            let meta = match meta {
+                Ok(meta) => meta,
+                Err(e) => {
+                    result.errors.push(WalkError {
+                        path: entry_path,
+                        error: e.into(),
+                    });
+                    continue;
+                }
+            };
+
+            let within_depth = child_depth >= opts.min_depth
+                && opts.max_depth.is_none_or(|max| child_depth <= max);
+            if within_depth {
+                result.entries.push(entry_path.clone());
+            }
+
+            if meta.is_dir() {
+                let at_max_depth = opts.max_depth.is_some_and(|max| child_depth >= max);
+                if at_max_depth {
+                    continue;
+                }
+                let id = dir_id(&meta);
+                if !visited.insert(id) {
+                    result.errors.push(WalkError {
+                        path: entry_path,
+                        error: anyhow::anyhow!("loop detected"),
+                    });
+                    continue;
+                }
+                stack.push((entry_path, child_depth));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_walk_dir() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_walk_dir");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let file_path = temp_dir.join("file");
+        File::create(&file_path)?;
+
+        let dir_path = temp_dir.join("dir");
+        create_dir_all(&dir_path)?;
+        let file_path_from_dir = dir_path.join("file");
+        File::create(&file_path_from_dir)?;
+
+        let mut result = walk(&temp_dir, &WalkOptions::new());
+        assert!(result.errors.is_empty());
+        result.entries.sort();
+        let mut expected = vec![file_path, dir_path, file_path_from_dir];
+        expected.sort();
+        assert_eq!(result.entries, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_max_depth() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_walk_max_depth");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let dir_path = temp_dir.join("dir");
+        create_dir_all(&dir_path)?;
+        File::create(dir_path.join("file"))?;
+
+        let result = walk(&temp_dir, &WalkOptions::new().max_depth(Some(1)));
+        assert!(result.errors.is_empty());
+        assert_eq!(result.entries, vec![dir_path]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_detects_symlink_cycle() -> anyhow::Result<()> {
+        let temp_dir = std::env::temp_dir().join("durs_test_walk_symlink_cycle");
+        if temp_dir.exists() {
+            remove_dir_all(&temp_dir)?;
+        }
+        create_dir_all(&temp_dir)?;
+
+        let loop_link = temp_dir.join("loop");
+        symlink(&temp_dir, &loop_link)?;
+
+        let result = walk(&temp_dir, &WalkOptions::new().follow_links(true));
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].error.to_string().contains("loop detected"));
+
+        Ok(())
+    }
+}